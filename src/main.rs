@@ -1,5 +1,70 @@
 pub mod three_d;
 
-fn main(){
-    three_d::run();
+use std::path::PathBuf;
+
+use three_d::{HeadlessOptions, Integrator, Spacetime};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        three_d::run();
+        return;
+    }
+
+    match parse_headless(&args) {
+        Ok(opts) => {
+            if let Err(e) = three_d::run_headless(&opts) {
+                eprintln!("Headless render failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!(
+                "usage: ui --headless <out.png> [--frames N] [--size WxH] \
+                 [--azimuth RAD] [--elevation RAD] [--radius M] [--orbit-step RAD] [--rk4 | --rk45] [--tol T] [--kerr] [--cpu]"
+            );
+            std::process::exit(2);
+        }
+    }
+}
+
+fn parse_headless(args: &[String]) -> Result<HeadlessOptions, String> {
+    let mut opts = HeadlessOptions::default();
+    let mut headless = false;
+    let mut it = args.iter();
+    while let Some(flag) = it.next() {
+        let mut value = || it.next().ok_or(format!("missing value for {flag}"));
+        match flag.as_str() {
+            "--headless" => {
+                headless = true;
+                opts.output = PathBuf::from(value()?);
+            }
+            "--frames" => opts.frames = parse(flag, value()?)?,
+            "--azimuth" => opts.azimuth = parse(flag, value()?)?,
+            "--elevation" => opts.elevation = parse(flag, value()?)?,
+            "--radius" => opts.radius = parse(flag, value()?)?,
+            "--orbit-step" => opts.orbit_step = parse(flag, value()?)?,
+            "--size" => {
+                let v = value()?;
+                let (w, h) = v.split_once('x').ok_or(format!("bad size {v}"))?;
+                opts.width = parse(flag, w)?;
+                opts.height = parse(flag, h)?;
+            }
+            "--rk4" => opts.settings.integrator = Integrator::Rk4,
+            "--rk45" => opts.settings.integrator = Integrator::Rk45,
+            "--tol" => opts.settings.tolerance = parse(flag, value()?)?,
+            "--kerr" => opts.settings.spacetime = Spacetime::Kerr,
+            "--cpu" => opts.force_cpu = true,
+            _ => return Err(format!("unknown argument {flag}")),
+        }
+    }
+    if !headless {
+        return Err("--headless <out.png> is required".to_string());
+    }
+    Ok(opts)
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, v: &str) -> Result<T, String> {
+    v.parse().map_err(|_| format!("bad value for {flag}: {v}"))
 }
@@ -7,6 +7,9 @@ pub const C: f64 = 299_792_458.0;
 // Black hole mass — Sagittarius A* (kg)
 pub const BH_MASS: f64 = 8.54e36;
 
+// Dimensionless spin a/M, used by the Kerr metric (Sgr A* estimates span ~0.5–0.94)
+pub const BH_SPIN: f32 = 0.9;
+
 // Grid layout
 pub const GRID_SIZE: i32 = 50;
 pub const SPACING: f32 = 1e10;
@@ -15,3 +18,17 @@ pub const SPACING: f32 = 1e10;
 // Far-field reference: ≈ 2*sqrt(r_s*(17.7e10 - r_s)) ≈ 9.1e10 m
 pub const WARP_OFFSET: f32 = -9.1e10;
 pub const GRID_Y_SHIFT: f32 = -3e10;
+
+// Geodesic tracer — must match the constants in `QUAD_FRAG_GEODESIC`
+pub const D_LAMBDA: f64 = 5e9;
+pub const MAX_STEPS: u32 = 3000;
+pub const ESCAPE_R: f64 = 1e12;
+pub const DISK_INNER: f64 = 2.2; // in units of r_s
+pub const DISK_OUTER: f64 = 5.2; // in units of r_s
+pub const POLE_EPS: f64 = 0.001;
+// Kerr rays stop at (1 + eps) r+, where Boyer–Lindquist coordinates freeze
+pub const HORIZON_EPS: f64 = 0.01;
+// RK45: default tolerance, and a step cap that keeps the straight segments used
+// for hit tests close to the curved path
+pub const RK45_TOLERANCE: f64 = 1e-6;
+pub const RK45_MAX_STEP: f64 = 2e10;
@@ -3,9 +3,16 @@
 use crate::three_d::constants::WARP_OFFSET;
 
 use super::constants::{GRID_SIZE, SPACING};
+use super::metric::Metric;
 use super::scene::{BlackHole, SceneObject};
 
-pub fn generate_grid(bh: &BlackHole, objects: &[SceneObject]) -> (Vec<[f32; 3]>, Vec<u32>) {
+/// Grid vertices warped by the black hole's `metric` plus each object's
+/// Schwarzschild warp, and line-list indices.
+pub fn generate_grid(
+    bh: &BlackHole,
+    metric: &dyn Metric,
+    objects: &[SceneObject],
+) -> (Vec<[f32; 3]>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
@@ -13,7 +20,7 @@ pub fn generate_grid(bh: &BlackHole, objects: &[SceneObject]) -> (Vec<[f32; 3]>,
         for x in 0..=GRID_SIZE {
             let world_x = (x - GRID_SIZE / 2) as f32 * SPACING;
             let world_z = (z - GRID_SIZE / 2) as f32 * SPACING;
-            let mut world_y = bh.warp_y(world_x, world_z);
+            let mut world_y = bh.warp_y(metric, world_x, world_z);
             for obj in objects {
                 world_y += obj.warp_contribution(world_x, world_z);
             }
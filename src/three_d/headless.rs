@@ -0,0 +1,284 @@
+//! Non-interactive rendering to PNG files.
+//!
+//! Renders on the GPU through a hidden window and an offscreen framebuffer;
+//! falls back to the CPU tracer when no GL context can be created.
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use glfw::Context;
+
+use super::camera::Camera;
+use super::grid::generate_grid;
+use super::math::{look_at, mat4_mul, mat4_transform, perspective};
+use super::renderer::GpuRenderer;
+use super::scene::{BlackHole, SceneObject, scene_objects};
+use super::tracer::{self, RgbImage, TraceSettings};
+
+pub struct HeadlessOptions {
+    pub width: u32,
+    pub height: u32,
+    /// Number of frames; the camera azimuth advances by `orbit_step` between frames.
+    pub frames: u32,
+    pub azimuth: f32,
+    pub elevation: f32,
+    pub radius: f32,
+    pub orbit_step: f32,
+    pub settings: TraceSettings,
+    /// Output file. With more than one frame the index is appended to the stem.
+    pub output: PathBuf,
+    /// Skip the GPU attempt and use the CPU tracer directly.
+    pub force_cpu: bool,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        let camera = Camera::new();
+        Self {
+            width: 800,
+            height: 600,
+            frames: 1,
+            azimuth: camera.azimuth,
+            elevation: camera.elevation,
+            radius: camera.radius,
+            orbit_step: 0.05,
+            settings: TraceSettings::default(),
+            output: PathBuf::from("frame.png"),
+            force_cpu: false,
+        }
+    }
+}
+
+/// Renders `opts.frames` frames and returns the paths written.
+pub fn run_headless(opts: &HeadlessOptions) -> io::Result<Vec<PathBuf>> {
+    let bh = BlackHole::new();
+    let objects = scene_objects();
+
+    let mut gpu = if opts.force_cpu {
+        None
+    } else {
+        GpuTarget::new(opts, &bh, &objects)
+    };
+    if gpu.is_none() {
+        eprintln!("No OpenGL context available, rendering with the CPU tracer");
+    }
+
+    let mut written = Vec::new();
+    for i in 0..opts.frames {
+        let mut camera = Camera::new();
+        camera.azimuth = opts.azimuth + i as f32 * opts.orbit_step;
+        camera.elevation = opts.elevation;
+        camera.radius = opts.radius;
+
+        let image = match gpu.as_mut() {
+            Some(target) => target.render(&camera, &bh, &opts.settings),
+            None => render_cpu(&camera, &bh, &objects, opts),
+        };
+
+        let path = frame_path(&opts.output, i, opts.frames);
+        write_png(&path, &image)?;
+        println!("Wrote {}", path.display());
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// `out.png` for a single frame, `out_0000.png`, `out_0001.png`, ... otherwise.
+fn frame_path(output: &Path, index: u32, frames: u32) -> PathBuf {
+    if frames <= 1 {
+        return output.to_path_buf();
+    }
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = output
+        .extension()
+        .unwrap_or("png".as_ref())
+        .to_string_lossy();
+    output.with_file_name(format!("{stem}_{index:04}.{ext}"))
+}
+
+pub fn write_png(path: &Path, image: &RgbImage) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, image.width, image.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&image.as_bytes())?;
+    Ok(())
+}
+
+/// Hidden GLFW window whose context renders into an offscreen framebuffer
+/// of exactly the requested size.
+struct GpuTarget {
+    renderer: GpuRenderer,
+    fbo: u32,
+    width: u32,
+    height: u32,
+    _window: glfw::PWindow,
+    _glfw: glfw::Glfw,
+}
+
+impl GpuTarget {
+    fn new(opts: &HeadlessOptions, bh: &BlackHole, objects: &[SceneObject]) -> Option<Self> {
+        let (width, height) = (opts.width, opts.height);
+        let mut glfw = glfw::init_no_callbacks().ok()?;
+        super::gl_window_hints(&mut glfw);
+        glfw.window_hint(glfw::WindowHint::Visible(false));
+        let (mut window, _events) =
+            glfw.create_window(width, height, "Black Hole Grid", glfw::WindowMode::Windowed)?;
+
+        window.make_current();
+        gl::load_with(|s| window.get_proc_address(s) as *const _);
+
+        let fbo = unsafe {
+            let (mut fbo, mut color, mut depth) = (0u32, 0u32, 0u32);
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+
+            gl::GenRenderbuffers(1, &mut color);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, color);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, width as i32, height as i32);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::RENDERBUFFER,
+                color,
+            );
+
+            gl::GenRenderbuffers(1, &mut depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+            gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH_COMPONENT24,
+                width as i32,
+                height as i32,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::RENDERBUFFER,
+                depth,
+            );
+
+            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                return None;
+            }
+            fbo
+        };
+
+        Some(Self {
+            renderer: GpuRenderer::new(bh, opts.settings.spacetime.metric(bh).as_ref(), objects),
+            fbo,
+            width,
+            height,
+            _window: window,
+            _glfw: glfw,
+        })
+    }
+
+    fn render(&mut self, camera: &Camera, bh: &BlackHole, settings: &TraceSettings) -> RgbImage {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut buf = vec![0u8; w * h * 3];
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            gl::Viewport(0, 0, w as i32, h as i32);
+            self.renderer
+                .draw(camera, bh, settings, w as f32 / h as f32);
+            gl::Finish();
+
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0,
+                0,
+                w as i32,
+                h as i32,
+                gl::RGB,
+                gl::UNSIGNED_BYTE,
+                buf.as_mut_ptr() as *mut _,
+            );
+        }
+
+        // GL rows run bottom to top
+        let pixels = buf
+            .chunks_exact(w * 3)
+            .rev()
+            .flat_map(|row| row.chunks_exact(3).map(|p| [p[0], p[1], p[2]]))
+            .collect();
+        RgbImage {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+}
+
+/// CPU tracer plus the grid overlay the GPU path draws on top.
+fn render_cpu(
+    camera: &Camera,
+    bh: &BlackHole,
+    objects: &[SceneObject],
+    opts: &HeadlessOptions,
+) -> RgbImage {
+    let settings = &opts.settings;
+    let (mut image, stats) = tracer::render(camera, bh, objects, settings, opts.width, opts.height);
+    println!("{:?}: {stats}", settings.integrator);
+    overlay_grid(&mut image, camera, bh, objects, settings);
+    image
+}
+
+/// Rasterises the warped grid lines with the same colour and alpha as `GRID_FRAG`.
+fn overlay_grid(
+    image: &mut RgbImage,
+    camera: &Camera,
+    bh: &BlackHole,
+    objects: &[SceneObject],
+    settings: &TraceSettings,
+) {
+    let (w, h) = (image.width as f32, image.height as f32);
+    let view = look_at(camera.position(), [0.0, 0.0, 0.0]);
+    let proj = perspective(settings.fov_y_deg.to_radians(), w / h, 1e9, 1e14);
+    let view_proj = mat4_mul(&proj, &view);
+
+    // Screen position, or None when outside the near/far planes
+    let project = |p: [f32; 3]| {
+        let c = mat4_transform(&view_proj, [p[0], p[1], p[2], 1.0]);
+        if c[3] <= 0.0 || (c[2] / c[3]).abs() > 1.0 {
+            return None;
+        }
+        Some(((c[0] / c[3] + 1.0) * 0.5 * w, (1.0 - c[1] / c[3]) * 0.5 * h))
+    };
+
+    let metric = settings.spacetime.metric(bh);
+    let (vertices, indices) = generate_grid(bh, metric.as_ref(), objects);
+    for seg in indices.chunks_exact(2) {
+        let (Some(a), Some(b)) = (
+            project(vertices[seg[0] as usize]),
+            project(vertices[seg[1] as usize]),
+        ) else {
+            continue;
+        };
+        let steps = (b.0 - a.0).abs().max((b.1 - a.1).abs()).ceil().max(1.0);
+        // Bound the walk for segments that project far off screen
+        if steps > 4.0 * (w + h) {
+            continue;
+        }
+        let mut last = usize::MAX;
+        for s in 0..=steps as u32 {
+            let t = s as f32 / steps;
+            let x = a.0 + (b.0 - a.0) * t;
+            let y = a.1 + (b.1 - a.1) * t;
+            if x < 0.0 || y < 0.0 || x >= w || y >= h {
+                continue;
+            }
+            let idx = y as usize * image.width as usize + x as usize;
+            if idx == last {
+                continue;
+            }
+            last = idx;
+            let px = &mut image.pixels[idx];
+            for c in px.iter_mut() {
+                *c = (0.7 * 0.7 * 255.0 + 0.3 * *c as f32).round() as u8;
+            }
+        }
+    }
+}
@@ -47,6 +47,17 @@ pub fn mat4_mul(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
     c
 }
 
+/// Column-major matrix times column vector.
+pub fn mat4_transform(m: &[f32; 16], v: [f32; 4]) -> [f32; 4] {
+    let mut out = [0.0f32; 4];
+    for (row, o) in out.iter_mut().enumerate() {
+        for k in 0..4 {
+            *o += m[k * 4 + row] * v[k];
+        }
+    }
+    out
+}
+
 fn vec3_sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
     [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
 }
@@ -0,0 +1,184 @@
+//! Spacetime metrics behind the CPU tracer and the warped grid.
+//!
+//! A `Metric` supplies the geodesic equations in its own state layout (see
+//! `State`), the horizon, conserved quantities and the embedding height used
+//! to draw the grid. Adding a spacetime means writing one impl here.
+
+use super::constants::{DISK_INNER, POLE_EPS};
+use super::kerr::Kerr;
+use super::scene::BlackHole;
+
+/// Geodesic state: position `[r, theta, phi]` followed by three metric-specific
+/// components. y-polar convention, as in the shader.
+pub type State = [f64; 6];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spacetime {
+    Schwarzschild,
+    /// Rotating black hole using `BlackHole::a`.
+    Kerr,
+}
+
+impl Spacetime {
+    pub fn metric(self, bh: &BlackHole) -> Box<dyn Metric> {
+        let r_s = bh.r_s as f64;
+        match self {
+            Spacetime::Schwarzschild => Box::new(Schwarzschild::new(r_s)),
+            Spacetime::Kerr => Box::new(Kerr::new(0.5 * r_s, bh.a as f64 * 0.5 * r_s)),
+        }
+    }
+}
+
+/// Constants of motion of a photon. Energy is per unit affine parameter scale,
+/// `carter` is zero for metrics without a Carter constant.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Conserved {
+    pub energy: f64,
+    /// Angular momentum about the spin (y) axis, L_z.
+    pub angular_momentum: f64,
+    pub carter: f64,
+}
+
+pub trait Metric: Sync {
+    /// State for a photon leaving Cartesian `origin` along unit `dir`.
+    fn initial_state(&self, origin: [f64; 3], dir: [f64; 3]) -> State;
+
+    /// Conserved quantities evaluated at `y`.
+    fn conserved(&self, y: &State) -> Conserved;
+
+    /// Geodesic equations dy/dλ, given the ray's conserved quantities.
+    fn rhs(&self, y: &State, c: &Conserved) -> State;
+
+    /// Outer event horizon radius.
+    fn horizon_radius(&self) -> f64;
+
+    /// Radius at which rays are stopped; step sizes shrink towards it.
+    fn capture_radius(&self) -> f64 {
+        self.horizon_radius()
+    }
+
+    /// True once the ray should be treated as captured.
+    fn inside_horizon(&self, y: &State) -> bool {
+        y[0] <= self.capture_radius()
+    }
+
+    /// Innermost stable circular orbit for prograde equatorial orbits.
+    fn isco_radius(&self) -> f64;
+
+    /// Inner edge of the accretion disk.
+    fn disk_inner_radius(&self) -> f64 {
+        self.isco_radius()
+    }
+
+    /// Height of the equatorial embedding surface at radius `r`, used for the grid.
+    fn embedding_height(&self, r: f64) -> f64;
+
+    /// Typical magnitude of each state component at `y`, so adaptive
+    /// integrators can compare errors in one dimensionless tolerance.
+    fn error_scale(&self, y: &State) -> State;
+
+    /// Coordinate fix-ups after each integration step.
+    fn post_step(&self, _y: &mut State) {}
+}
+
+/// Spherical position `[r, theta, phi]` and coordinate velocity
+/// `[dr, dtheta, dphi]` of a Cartesian ray.
+pub fn spherical_ray(origin: [f64; 3], dir: [f64; 3]) -> ([f64; 3], [f64; 3]) {
+    let r = (origin[0] * origin[0] + origin[1] * origin[1] + origin[2] * origin[2]).sqrt();
+    let theta = (origin[1] / r).clamp(-1.0, 1.0).acos();
+    let phi = origin[2].atan2(origin[0]);
+
+    let sin_theta_raw = theta.sin();
+    let sin_t = sin_theta_raw.max(1e-6);
+    let cos_t = theta.cos();
+    let (sin_p, cos_p) = phi.sin_cos();
+
+    let [dx, dy, dz] = dir;
+    let dr = sin_t * cos_p * dx + cos_t * dy + sin_t * sin_p * dz;
+    let dtheta = (cos_t * cos_p * dx - sin_t * dy + cos_t * sin_p * dz) / r;
+    let mut dphi = (-sin_p * dx + cos_p * dz) / (r * sin_t);
+    if sin_theta_raw.abs() < POLE_EPS {
+        dphi = 0.0;
+    }
+    ([r, theta, phi], [dr, dtheta, dphi])
+}
+
+/// Non-rotating black hole. State is `[r, theta, phi, dr, dtheta, dphi]`,
+/// derivatives with respect to the affine parameter.
+pub struct Schwarzschild {
+    pub r_s: f64,
+}
+
+impl Schwarzschild {
+    pub fn new(r_s: f64) -> Self {
+        Self { r_s }
+    }
+}
+
+impl Metric for Schwarzschild {
+    fn initial_state(&self, origin: [f64; 3], dir: [f64; 3]) -> State {
+        let ([r, theta, phi], [dr, dtheta, dphi]) = spherical_ray(origin, dir);
+        [r, theta, phi, dr, dtheta, dphi]
+    }
+
+    fn conserved(&self, y: &State) -> Conserved {
+        let [r, theta, _, dr, dtheta, dphi] = *y;
+        let sin_t = theta.sin().max(1e-6);
+        let f = 1.0 - self.r_s / r;
+        // Null condition: f (dt/dλ)² = dr²/f + r²(dθ² + sin²θ dφ²)
+        let dt_dl =
+            ((dr * dr / f + r * r * (dtheta * dtheta + sin_t * sin_t * dphi * dphi)) / f).sqrt();
+        let l_z = r * r * sin_t * sin_t * dphi;
+        let l2 = r.powi(4) * (dtheta * dtheta + sin_t * sin_t * dphi * dphi);
+        Conserved {
+            energy: f * dt_dl,
+            angular_momentum: l_z,
+            carter: l2 - l_z * l_z,
+        }
+    }
+
+    fn rhs(&self, y: &State, c: &Conserved) -> State {
+        let r_s = self.r_s;
+        let [r, theta, _, dr, dtheta, dphi] = *y;
+        let f = 1.0 - r_s / r;
+        let dt_dl = c.energy / f;
+        let sin_t = theta.sin().max(1e-6);
+        let cos_t = theta.cos();
+        let d2r = -(r_s / (2.0 * r * r)) * f * dt_dl * dt_dl
+            + (r_s / (2.0 * r * r * f)) * dr * dr
+            + r * f * (dtheta * dtheta + sin_t * sin_t * dphi * dphi);
+        let d2theta = -2.0 * dr * dtheta / r + sin_t * cos_t * dphi * dphi;
+        let d2phi = -2.0 * dr * dphi / r - 2.0 * (cos_t / sin_t) * dtheta * dphi;
+        [dr, dtheta, dphi, d2r, d2theta, d2phi]
+    }
+
+    fn horizon_radius(&self) -> f64 {
+        self.r_s
+    }
+
+    fn isco_radius(&self) -> f64 {
+        3.0 * self.r_s
+    }
+
+    fn disk_inner_radius(&self) -> f64 {
+        DISK_INNER * self.r_s
+    }
+
+    /// Flamm's paraboloid, z = 2 sqrt(r_s (r - r_s)).
+    fn embedding_height(&self, r: f64) -> f64 {
+        2.0 * (self.r_s * (r - self.r_s)).sqrt()
+    }
+
+    /// Angles are O(1), dr is O(1) for a unit initial direction, angular rates O(1/r).
+    fn error_scale(&self, y: &State) -> State {
+        let r = y[0];
+        [r, 1.0, 1.0, 1.0, 1.0 / r, 1.0 / r]
+    }
+
+    /// Near the polar axis: zero dphi to avoid the coordinate singularity.
+    fn post_step(&self, y: &mut State) {
+        if y[1].sin().abs() < POLE_EPS {
+            y[5] = 0.0;
+        }
+    }
+}
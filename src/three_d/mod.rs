@@ -4,31 +4,42 @@ mod camera;
 mod constants;
 mod gl_utils;
 mod grid;
+mod headless;
+mod kerr;
 mod math;
+mod metric;
+mod renderer;
 mod scene;
 mod shaders;
+mod tracer;
 
 use glfw::{Action, Context, Key, MouseButton, WindowEvent};
 
 use camera::Camera;
-use gl_utils::create_program;
-use grid::generate_grid;
-use math::{camera_basis, look_at, mat4_mul, perspective};
+use renderer::GpuRenderer;
 use scene::scene_objects;
-use shaders::{GRID_FRAG, GRID_VERT, QUAD_FRAG_GEODESIC, QUAD_VERT};
 
 pub use camera::Camera as ThreeDCamera;
+pub use headless::{HeadlessOptions, run_headless};
+pub use kerr::Kerr;
+pub use metric::{Conserved, Metric, Schwarzschild, Spacetime, State};
 pub use scene::{BlackHole, GridObject};
+pub use tracer::{Integrator, RayTrace, RgbImage, StepStats, TraceSettings, render as render_cpu};
 
-pub fn run() {
-    // Program setup
-    let mut glfw = glfw::init_no_callbacks().unwrap();
+/// OpenGL 3.3 core context hints shared by the viewer and headless windows.
+fn gl_window_hints(glfw: &mut glfw::Glfw) {
     glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
     glfw.window_hint(glfw::WindowHint::OpenGlProfile(
         glfw::OpenGlProfileHint::Core,
     ));
     #[cfg(target_os = "macos")]
     glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
+}
+
+pub fn run() {
+    // Program setup
+    let mut glfw = glfw::init_no_callbacks().unwrap();
+    gl_window_hints(&mut glfw);
 
     // Window setup
     let (mut window, events) = glfw
@@ -45,127 +56,14 @@ pub fn run() {
 
     // -- Scene --
     let bh = BlackHole::new();
-    let bh_r_s: f32 = bh.r_s;
 
     // -- GPU setup --
-    let (program, vao, index_count, quad_program, quad_vao) = unsafe {
-        let program = create_program(GRID_VERT, GRID_FRAG);
-        let quad_program = create_program(QUAD_VERT, QUAD_FRAG_GEODESIC);
-
-        // Grid geometry
-        let (vertices, indices) = generate_grid(&bh, &scene_objects());
-        let index_count = indices.len() as i32;
-
-        // Allocate GPU buffers
-        let (mut vao, mut vbo, mut ebo) = (0u32, 0u32, 0u32);
-        gl::GenVertexArrays(1, &mut vao);
-        gl::GenBuffers(1, &mut vbo);
-        gl::GenBuffers(1, &mut ebo);
-
-        // Start VAO
-        gl::BindVertexArray(vao);
-
-        // Move indices and vertices data to GPU
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            (vertices.len() * std::mem::size_of::<[f32; 3]>()) as isize,
-            vertices.as_ptr() as *const _,
-            gl::DYNAMIC_DRAW,
-        );
-        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
-        gl::BufferData(
-            gl::ELEMENT_ARRAY_BUFFER,
-            (indices.len() * std::mem::size_of::<u32>()) as isize,
-            indices.as_ptr() as *const _,
-            gl::STATIC_DRAW,
-        );
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 12, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
-        gl::BindVertexArray(0);
-
-        // Fullscreen quad
-        let quad_verts: [f32; 24] = [
-            -1.0, 1.0, 0.0, 1.0, -1.0, -1.0, 0.0, 0.0, 1.0, -1.0, 1.0, 0.0, -1.0, 1.0, 0.0, 1.0,
-            1.0, -1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0,
-        ];
-
-        // Allocate GPU buffers and move to GPU
-        let mut quad_vao = 0u32;
-        let mut quad_vbo = 0u32;
-        gl::GenVertexArrays(1, &mut quad_vao);
-        gl::GenBuffers(1, &mut quad_vbo);
-        gl::BindVertexArray(quad_vao);
-        gl::BindBuffer(gl::ARRAY_BUFFER, quad_vbo);
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            (quad_verts.len() * std::mem::size_of::<f32>()) as isize,
-            quad_verts.as_ptr() as *const _,
-            gl::STATIC_DRAW,
-        );
-        gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 16, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
-        gl::VertexAttribPointer(
-            1,
-            2,
-            gl::FLOAT,
-            gl::FALSE,
-            16,
-            (2 * std::mem::size_of::<f32>()) as *const _,
-        );
-        gl::EnableVertexAttribArray(1);
-        gl::BindVertexArray(0);
-
-        (program, vao, index_count, quad_program, quad_vao)
-    };
-
-    // Uniform locations
-    let loc_vp =
-        unsafe { gl::GetUniformLocation(program, b"viewProj\0".as_ptr() as *const _) };
-    let loc_use_rk4 =
-        unsafe { gl::GetUniformLocation(quad_program, b"useRK4\0".as_ptr() as *const _) };
-
-    let (loc_cam_pos, loc_cam_right, loc_cam_up, loc_cam_fwd, loc_thfov, loc_aspect, loc_rs) =
-        unsafe {
-            (
-                gl::GetUniformLocation(quad_program, b"camPos\0".as_ptr() as *const _),
-                gl::GetUniformLocation(quad_program, b"camRight\0".as_ptr() as *const _),
-                gl::GetUniformLocation(quad_program, b"camUp\0".as_ptr() as *const _),
-                gl::GetUniformLocation(quad_program, b"camForward\0".as_ptr() as *const _),
-                gl::GetUniformLocation(quad_program, b"tanHalfFov\0".as_ptr() as *const _),
-                gl::GetUniformLocation(quad_program, b"aspect\0".as_ptr() as *const _),
-                gl::GetUniformLocation(quad_program, b"r_s\0".as_ptr() as *const _),
-            )
-        };
-
-    // -- Upload static scene objects to the geodesic shader (once, before the loop) --
     let objects = scene_objects();
-    {
-        let loc_num = unsafe { gl::GetUniformLocation(quad_program, b"numObjects\0".as_ptr() as *const _) };
-        let loc_pr  = unsafe { gl::GetUniformLocation(quad_program, b"objPosRadius[0]\0".as_ptr() as *const _) };
-        let loc_col = unsafe { gl::GetUniformLocation(quad_program, b"objColor[0]\0".as_ptr() as *const _) };
-
-        let mut pr_data: Vec<f32> = Vec::new();
-        let mut col_data: Vec<f32> = Vec::new();
-        for o in &objects {
-            pr_data.extend_from_slice(&[o.position[0], o.position[1], o.position[2], o.radius]);
-            col_data.extend_from_slice(&[o.color[0], o.color[1], o.color[2], 0.0]);
-        }
-
-        unsafe {
-            gl::UseProgram(quad_program);
-            gl::Uniform1i(loc_num, objects.len() as i32);
-            if !objects.is_empty() {
-                gl::Uniform4fv(loc_pr,  objects.len() as i32, pr_data.as_ptr());
-                gl::Uniform4fv(loc_col, objects.len() as i32, col_data.as_ptr());
-            }
-        }
-    }
+    let mut settings = TraceSettings::default();
+    let renderer = GpuRenderer::new(&bh, settings.spacetime.metric(&bh).as_ref(), &objects);
 
     // Camera perspectives, move to GPU
-    let mut use_rk4 = false;
     let mut camera = Camera::new();
-    let tan_hfov = (60.0_f32.to_radians() * 0.5).tan();
     let aspect = 800.0 / 600.0_f32;
 
     while !window.should_close() {
@@ -177,10 +75,19 @@ pub fn run() {
                 WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
                     window.set_should_close(true)
                 }
-                // Handle toggle between Euler and RK4
+                // Cycle Euler -> RK4 -> RK45
                 WindowEvent::Key(Key::I, _, Action::Press, _) => {
-                    use_rk4 = !use_rk4;
-                    println!("Integrator: {}", if use_rk4 { "RK4" } else { "Euler" });
+                    settings.integrator = settings.integrator.next();
+                    println!("Integrator: {:?}", settings.integrator);
+                }
+                // Handle toggle between Schwarzschild and Kerr
+                WindowEvent::Key(Key::K, _, Action::Press, _) => {
+                    settings.spacetime = match settings.spacetime {
+                        Spacetime::Schwarzschild => Spacetime::Kerr,
+                        Spacetime::Kerr => Spacetime::Schwarzschild,
+                    };
+                    renderer.update_grid(&bh, settings.spacetime.metric(&bh).as_ref(), &objects);
+                    println!("Spacetime: {:?} (a = {})", settings.spacetime, bh.a);
                 }
 
                 // Drag event - Pressed
@@ -218,43 +125,7 @@ pub fn run() {
             }
         }
 
-        let pos = camera.position();
-        let (right, up, fwd) = camera_basis(pos);
-
-        unsafe {
-            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-
-            gl::Enable(gl::BLEND);
-            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
-
-            // Geodesic ray-traced image
-            gl::Disable(gl::DEPTH_TEST);
-            gl::UseProgram(quad_program);
-            gl::Uniform3fv(loc_cam_pos, 1, pos.as_ptr());
-            gl::Uniform3fv(loc_cam_right, 1, right.as_ptr());
-            gl::Uniform3fv(loc_cam_up, 1, up.as_ptr());
-            gl::Uniform3fv(loc_cam_fwd, 1, fwd.as_ptr());
-            gl::Uniform1f(loc_thfov, tan_hfov);
-            gl::Uniform1f(loc_aspect, aspect);
-            gl::Uniform1f(loc_rs, bh_r_s);
-            gl::Uniform1i(loc_use_rk4, use_rk4 as i32);
-            gl::BindVertexArray(quad_vao);
-            gl::DrawArrays(gl::TRIANGLES, 0, 6);
-            gl::BindVertexArray(0);
-            gl::Enable(gl::DEPTH_TEST);
-
-            // Warped grid overlay
-            let view = look_at(pos, [0.0, 0.0, 0.0]);
-            let proj = perspective(60.0_f32.to_radians(), 800.0 / 600.0, 1e9, 1e14);
-            let view_proj = mat4_mul(&proj, &view);
-
-            gl::UseProgram(program);
-            gl::UniformMatrix4fv(loc_vp, 1, gl::FALSE, view_proj.as_ptr());
-            gl::BindVertexArray(vao);
-            gl::DrawElements(gl::LINES, index_count, gl::UNSIGNED_INT, std::ptr::null());
-            gl::BindVertexArray(0);
-        }
+        renderer.draw(&camera, &bh, &settings, aspect);
 
         window.swap_buffers();
     }
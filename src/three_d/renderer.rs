@@ -0,0 +1,243 @@
+//! GPU state for one frame: the geodesic quad and the warped grid overlay.
+
+use gl::types::*;
+
+use super::camera::Camera;
+use super::gl_utils::create_program;
+use super::grid::generate_grid;
+use super::math::{camera_basis, look_at, mat4_mul, perspective};
+use super::metric::{Metric, Spacetime};
+use super::scene::{BlackHole, SceneObject};
+use super::shaders::{GRID_FRAG, GRID_VERT, QUAD_FRAG_GEODESIC, QUAD_VERT};
+use super::tracer::{Integrator, TraceSettings};
+
+pub struct GpuRenderer {
+    program: GLuint,
+    vao: GLuint,
+    vbo: GLuint,
+    index_count: i32,
+    quad_program: GLuint,
+    quad_vao: GLuint,
+
+    loc_vp: GLint,
+    loc_integrator: GLint,
+    loc_tolerance: GLint,
+    loc_cam_pos: GLint,
+    loc_cam_right: GLint,
+    loc_cam_up: GLint,
+    loc_cam_fwd: GLint,
+    loc_thfov: GLint,
+    loc_aspect: GLint,
+    loc_rs: GLint,
+    loc_disk_inner: GLint,
+    loc_use_kerr: GLint,
+    loc_spin: GLint,
+}
+
+impl GpuRenderer {
+    /// Compiles the shaders and uploads the grid and scene objects.
+    /// Needs a current GL context with loaded function pointers.
+    pub fn new(bh: &BlackHole, metric: &dyn Metric, objects: &[SceneObject]) -> Self {
+        let (program, vao, vbo, index_count, quad_program, quad_vao) = unsafe {
+            let program = create_program(GRID_VERT, GRID_FRAG);
+            let quad_program = create_program(QUAD_VERT, QUAD_FRAG_GEODESIC);
+
+            // Grid geometry
+            let (vertices, indices) = generate_grid(bh, metric, objects);
+            let index_count = indices.len() as i32;
+
+            // Allocate GPU buffers
+            let (mut vao, mut vbo, mut ebo) = (0u32, 0u32, 0u32);
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::GenBuffers(1, &mut ebo);
+
+            // Start VAO
+            gl::BindVertexArray(vao);
+
+            // Move indices and vertices data to GPU
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (vertices.len() * std::mem::size_of::<[f32; 3]>()) as isize,
+                vertices.as_ptr() as *const _,
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                (indices.len() * std::mem::size_of::<u32>()) as isize,
+                indices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 12, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+            gl::BindVertexArray(0);
+
+            // Fullscreen quad
+            let quad_verts: [f32; 24] = [
+                -1.0, 1.0, 0.0, 1.0, -1.0, -1.0, 0.0, 0.0, 1.0, -1.0, 1.0, 0.0, -1.0, 1.0, 0.0,
+                1.0, 1.0, -1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0,
+            ];
+
+            // Allocate GPU buffers and move to GPU
+            let mut quad_vao = 0u32;
+            let mut quad_vbo = 0u32;
+            gl::GenVertexArrays(1, &mut quad_vao);
+            gl::GenBuffers(1, &mut quad_vbo);
+            gl::BindVertexArray(quad_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, quad_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (quad_verts.len() * std::mem::size_of::<f32>()) as isize,
+                quad_verts.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 16, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(
+                1,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                16,
+                (2 * std::mem::size_of::<f32>()) as *const _,
+            );
+            gl::EnableVertexAttribArray(1);
+            gl::BindVertexArray(0);
+
+            (program, vao, vbo, index_count, quad_program, quad_vao)
+        };
+
+        // Uniform locations
+        let renderer = unsafe {
+            Self {
+                program,
+                vao,
+                vbo,
+                index_count,
+                quad_program,
+                quad_vao,
+                loc_vp: gl::GetUniformLocation(program, c"viewProj".as_ptr()),
+                loc_integrator: gl::GetUniformLocation(quad_program, c"integrator".as_ptr()),
+                loc_tolerance: gl::GetUniformLocation(quad_program, c"tolerance".as_ptr()),
+                loc_cam_pos: gl::GetUniformLocation(quad_program, c"camPos".as_ptr()),
+                loc_cam_right: gl::GetUniformLocation(quad_program, c"camRight".as_ptr()),
+                loc_cam_up: gl::GetUniformLocation(quad_program, c"camUp".as_ptr()),
+                loc_cam_fwd: gl::GetUniformLocation(quad_program, c"camForward".as_ptr()),
+                loc_thfov: gl::GetUniformLocation(quad_program, c"tanHalfFov".as_ptr()),
+                loc_aspect: gl::GetUniformLocation(quad_program, c"aspect".as_ptr()),
+                loc_rs: gl::GetUniformLocation(quad_program, c"r_s".as_ptr()),
+                loc_disk_inner: gl::GetUniformLocation(quad_program, c"diskInner".as_ptr()),
+                loc_use_kerr: gl::GetUniformLocation(quad_program, c"useKerr".as_ptr()),
+                loc_spin: gl::GetUniformLocation(quad_program, c"spin".as_ptr()),
+            }
+        };
+        renderer.upload_objects(objects);
+        renderer
+    }
+
+    /// Regenerates the grid vertices, e.g. after switching metric.
+    pub fn update_grid(&self, bh: &BlackHole, metric: &dyn Metric, objects: &[SceneObject]) {
+        let (vertices, _) = generate_grid(bh, metric, objects);
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferSubData(
+                gl::ARRAY_BUFFER,
+                0,
+                (vertices.len() * std::mem::size_of::<[f32; 3]>()) as isize,
+                vertices.as_ptr() as *const _,
+            );
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+
+    /// Uploads scene objects to the geodesic shader.
+    fn upload_objects(&self, objects: &[SceneObject]) {
+        let loc_num = unsafe { gl::GetUniformLocation(self.quad_program, c"numObjects".as_ptr()) };
+        let loc_pr =
+            unsafe { gl::GetUniformLocation(self.quad_program, c"objPosRadius[0]".as_ptr()) };
+        let loc_col = unsafe { gl::GetUniformLocation(self.quad_program, c"objColor[0]".as_ptr()) };
+
+        let mut pr_data: Vec<f32> = Vec::new();
+        let mut col_data: Vec<f32> = Vec::new();
+        for o in objects {
+            pr_data.extend_from_slice(&[o.position[0], o.position[1], o.position[2], o.radius]);
+            col_data.extend_from_slice(&[o.color[0], o.color[1], o.color[2], 0.0]);
+        }
+
+        unsafe {
+            gl::UseProgram(self.quad_program);
+            gl::Uniform1i(loc_num, objects.len() as i32);
+            if !objects.is_empty() {
+                gl::Uniform4fv(loc_pr, objects.len() as i32, pr_data.as_ptr());
+                gl::Uniform4fv(loc_col, objects.len() as i32, col_data.as_ptr());
+            }
+        }
+    }
+
+    /// Draws the ray-traced image and the grid overlay into the bound framebuffer.
+    pub fn draw(&self, camera: &Camera, bh: &BlackHole, settings: &TraceSettings, aspect: f32) {
+        let fov_y_deg = settings.fov_y_deg;
+        let pos = camera.position();
+        let (right, up, fwd) = camera_basis(pos);
+        let tan_hfov = (fov_y_deg.to_radians() * 0.5).tan();
+
+        unsafe {
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            // Geodesic ray-traced image
+            gl::Disable(gl::DEPTH_TEST);
+            gl::UseProgram(self.quad_program);
+            gl::Uniform3fv(self.loc_cam_pos, 1, pos.as_ptr());
+            gl::Uniform3fv(self.loc_cam_right, 1, right.as_ptr());
+            gl::Uniform3fv(self.loc_cam_up, 1, up.as_ptr());
+            gl::Uniform3fv(self.loc_cam_fwd, 1, fwd.as_ptr());
+            gl::Uniform1f(self.loc_thfov, tan_hfov);
+            gl::Uniform1f(self.loc_aspect, aspect);
+            gl::Uniform1f(self.loc_rs, bh.r_s);
+            gl::Uniform1f(
+                self.loc_disk_inner,
+                settings.spacetime.metric(bh).disk_inner_radius() as f32,
+            );
+            gl::Uniform1i(
+                self.loc_use_kerr,
+                (settings.spacetime == Spacetime::Kerr) as i32,
+            );
+            gl::Uniform1f(self.loc_spin, bh.a);
+            gl::Uniform1i(
+                self.loc_integrator,
+                match settings.integrator {
+                    Integrator::Euler => 0,
+                    Integrator::Rk4 => 1,
+                    Integrator::Rk45 => 2,
+                },
+            );
+            gl::Uniform1f(self.loc_tolerance, settings.tolerance as f32);
+            gl::BindVertexArray(self.quad_vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            gl::BindVertexArray(0);
+            gl::Enable(gl::DEPTH_TEST);
+
+            // Warped grid overlay
+            let view = look_at(pos, [0.0, 0.0, 0.0]);
+            let proj = perspective(fov_y_deg.to_radians(), aspect, 1e9, 1e14);
+            let view_proj = mat4_mul(&proj, &view);
+
+            gl::UseProgram(self.program);
+            gl::UniformMatrix4fv(self.loc_vp, 1, gl::FALSE, view_proj.as_ptr());
+            gl::BindVertexArray(self.vao);
+            gl::DrawElements(
+                gl::LINES,
+                self.index_count,
+                gl::UNSIGNED_INT,
+                std::ptr::null(),
+            );
+            gl::BindVertexArray(0);
+        }
+    }
+}
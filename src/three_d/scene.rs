@@ -1,12 +1,15 @@
 //! Black hole and grid-warping objects.
 
-use super::constants::{BH_MASS, C, G, GRID_Y_SHIFT};
+use super::constants::{BH_MASS, BH_SPIN, C, G, GRID_Y_SHIFT};
+use super::metric::{Metric, Schwarzschild};
 
 pub struct BlackHole {
     pub x: f32,
     pub z: f32,
     pub mass: f64,
     pub r_s: f32,
+    /// Dimensionless spin a/M in [-1, 1]; only the Kerr path uses it.
+    pub a: f32,
 }
 
 impl BlackHole {
@@ -17,20 +20,32 @@ impl BlackHole {
             z: 0.0,
             mass: BH_MASS,
             r_s,
+            a: BH_SPIN,
         }
     }
 
     // Grid warping at (x, y) due to this blackhole
-    pub fn warp_y(&self, x: f32, z: f32) -> f32 {
-        let dx = x - self.x;
-        let dy = GRID_Y_SHIFT;
-        let dz = z - self.z;
-        let dist = (dx * dx + dy * dy + dz * dz).sqrt();
-        let delta_y = 2.0 * (self.r_s * (dist - self.r_s)).sqrt();
-        GRID_Y_SHIFT + delta_y
+    pub fn warp_y(&self, metric: &dyn Metric, x: f32, z: f32) -> f32 {
+        GRID_Y_SHIFT + embedding_at(metric, self.x, self.z, x, z)
     }
 }
 
+impl Default for BlackHole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Embedding height of `metric` centred on (cx, cz), sampled at (x, z) on the
+/// grid plane `GRID_Y_SHIFT` below the centre.
+fn embedding_at(metric: &dyn Metric, cx: f32, cz: f32, x: f32, z: f32) -> f32 {
+    let dx = x - cx;
+    let dy = GRID_Y_SHIFT;
+    let dz = z - cz;
+    let dist = (dx * dx + dy * dy + dz * dz).sqrt();
+    metric.embedding_height(dist as f64) as f32
+}
+
 /// Extra massive object that warps the grid (e.g. star). Position in x-z plane.
 pub struct GridObject {
     pub x: f32,
@@ -45,13 +60,8 @@ impl GridObject {
 
     /// Flamm paraboloid warp contribution at (world_x, world_z), added to grid y.
     pub fn warp_contribution(&self, world_x: f32, world_z: f32) -> f32 {
-        let r_s = self.r_s();
-        let dx = world_x - self.x;
-        let dz = world_z - self.z;
-        let dy = GRID_Y_SHIFT;
-        let dist = (dx * dx + dy * dy + dz * dz).sqrt();
-        let delta_y = 2.0 * (r_s * (dist - r_s)).sqrt();
-        delta_y
+        let metric = Schwarzschild::new(self.r_s() as f64);
+        embedding_at(&metric, self.x, self.z, world_x, world_z)
     }
 }
 
@@ -77,13 +87,8 @@ impl SceneObject {
 
     /// Flamm paraboloid warp contribution at (world_x, world_z), added to grid y.
     pub fn warp_contribution(&self, world_x: f32, world_z: f32) -> f32 {
-        let r_s = self.r_s();
-        let dx = world_x - self.position[0];
-        let dz = world_z - self.position[2];
-        let dy = GRID_Y_SHIFT;
-        let dist = (dx * dx + dy * dy + dz * dz).sqrt();
-        let delta_y = 2.0 * (r_s * (dist - r_s)).sqrt();
-        delta_y
+        let metric = Schwarzschild::new(self.r_s() as f64);
+        embedding_at(&metric, self.position[0], self.position[2], world_x, world_z)
     }
 }
 
@@ -28,7 +28,7 @@ pub const QUAD_VERT: &str = r#"
     }
 "#;
 
-/// Schwarzschild null-geodesic ray tracer. y-polar convention.
+/// Schwarzschild / Kerr null-geodesic ray tracer. y-polar convention.
 pub const QUAD_FRAG_GEODESIC: &str = r#"
     #version 330 core
     in  vec2  vTex;
@@ -41,6 +41,9 @@ pub const QUAD_FRAG_GEODESIC: &str = r#"
     uniform float tanHalfFov;
     uniform float aspect;
     uniform float r_s;
+    uniform float diskInner;  // inner disk edge (metres), ISCO for Kerr
+    uniform int   useKerr;    // 1 = Kerr, 0 = Schwarzschild
+    uniform float spin;       // dimensionless a/M
 
     const int   MAX_OBJECTS = 8;
     uniform int  numObjects;
@@ -50,9 +53,10 @@ pub const QUAD_FRAG_GEODESIC: &str = r#"
     const float D_LAMBDA   = 5e9;
     const int   MAX_STEPS  = 3000;
     const float ESCAPE_R   = 1e12;
-    const float DISK_INNER = 2.2;
     const float DISK_OUTER = 5.2;
     const float POLE_EPS   = 0.001;  // near polar axis: zero dphi to avoid singularity
+    const float HORIZON_EPS = 0.01;  // Kerr: stop at (1 + eps) r+
+    const float RK45_MAX_STEP = 2e10; // below the smallest object radius
 
     void geodesic_rhs(float r, float theta,
                       float dr, float dtheta, float dphi, float E,
@@ -63,12 +67,13 @@ pub const QUAD_FRAG_GEODESIC: &str = r#"
         float cos_t = cos(theta);
         d2r     = -(r_s / (2.0*r*r)) * f * dt_dl*dt_dl
                   + (r_s / (2.0*r*r*f)) * dr*dr
-                  + r * (dtheta*dtheta + sin_t*sin_t*dphi*dphi);
+                  + r * f * (dtheta*dtheta + sin_t*sin_t*dphi*dphi);
         d2theta = -2.0*dr*dtheta/r + sin_t*cos_t*dphi*dphi;
         d2phi   = -2.0*dr*dphi/r   - 2.0*(cos_t/sin_t)*dtheta*dphi;
     }
 
-    uniform int useRK4;  // 1 = RK4, 0 = Euler
+    uniform int   integrator; // 0 = Euler, 1 = RK4, 2 = RK45
+    uniform float tolerance;  // RK45 per-step error tolerance
     
     // Euler step - take step using derivative at start of interval
     void euler_step(inout float r, inout float theta, inout float phi,
@@ -122,6 +127,134 @@ pub const QUAD_FRAG_GEODESIC: &str = r#"
         dphi   += h/6.0*(k1vp + 2.0*k2vp + 2.0*k3vp + k4vp);
     }
 
+    // Kerr null geodesic in Boyer–Lindquist coordinates, Hamiltonian form with
+    // E = 1 and lengths in units of M. x = (r, theta, phi), p = (p_r, p_theta),
+    // b = L/E is conserved. Rays run forward in time from the camera, the time
+    // reverse of the real photon, so they see the mirrored spin -a (see kerr.rs).
+    void kerr_rhs(vec3 x, vec2 p, float b, out vec3 dx, out vec2 dp) {
+        float r     = x.x;
+        float sin_t = max(sin(x.y), 1e-6);
+        float cos_t = cos(x.y);
+        float a  = -spin;
+        float a2 = a*a, r2 = r*r, s2 = sin_t*sin_t;
+        float sigma = r2 + a2*cos_t*cos_t;
+        float delta = r2 - 2.0*r + a2;
+        float kappa = p.y*p.y + b*b/s2 + a2*s2;
+        dx.x = delta * p.x / sigma;
+        dx.y = p.y / sigma;
+        dx.z = (2.0*a*r + (sigma - 2.0*r) * b / s2) / (sigma * delta);
+        dp.x = (2.0*r*(r2 + a2) - (r - 1.0)*kappa - 2.0*a*b) / (sigma * delta)
+               - 2.0*p.x*p.x*(r - 1.0) / sigma;
+        dp.y = sin_t*cos_t*(b*b/(s2*s2) - a2) / sigma;
+    }
+
+    void kerr_euler_step(inout vec3 x, inout vec2 p, float b, float h) {
+        vec3 dx; vec2 dp;
+        kerr_rhs(x, p, b, dx, dp);
+        x += h * dx;
+        p += h * dp;
+    }
+
+    void kerr_rk4_step(inout vec3 x, inout vec2 p, float b, float h) {
+        vec3 k1x, k2x, k3x, k4x;
+        vec2 k1p, k2p, k3p, k4p;
+        kerr_rhs(x,              p,              b, k1x, k1p);
+        kerr_rhs(x + 0.5*h*k1x,  p + 0.5*h*k1p,  b, k2x, k2p);
+        kerr_rhs(x + 0.5*h*k2x,  p + 0.5*h*k2p,  b, k3x, k3p);
+        kerr_rhs(x + h*k3x,      p + h*k3p,      b, k4x, k4p);
+        x += h/6.0*(k1x + 2.0*k2x + 2.0*k3x + k4x);
+        p += h/6.0*(k1p + 2.0*k2p + 2.0*k3p + k4p);
+    }
+
+    // Momenta (p_r, p_theta) and b = L/E from a coordinate velocity, in units
+    // of M. dt/dlambda comes from the null condition.
+    void kerr_init(float r, float theta, float dr, float dtheta, float dphi,
+                   out vec2 p, out float b) {
+        float sin_t = max(sin(theta), 1e-6);
+        float cos_t = cos(theta);
+        float a  = -spin;
+        float a2 = a*a, r2 = r*r, s2 = sin_t*sin_t;
+        float sigma = r2 + a2*cos_t*cos_t;
+        float delta = r2 - 2.0*r + a2;
+        float g_tt = -(1.0 - 2.0*r/sigma);
+        float g_tp = -2.0*a*r*s2/sigma;
+        float g_rr = sigma/delta;
+        float g_pp = (r2 + a2 + 2.0*a2*r*s2/sigma) * s2;
+        float qb = 2.0*g_tp*dphi;
+        float qc = g_rr*dr*dr + sigma*dtheta*dtheta + g_pp*dphi*dphi;
+        float dt = (-qb - sqrt(qb*qb - 4.0*g_tt*qc)) / (2.0*g_tt);
+        float E  = -(g_tt*dt + g_tp*dphi);
+        p = vec2(g_rr*dr, sigma*dtheta) / E;
+        b = (g_tp*dt + g_pp*dphi) / E;
+    }
+
+    // Derivatives of a packed state for the active metric. Schwarzschild:
+    // x = (r, theta, phi), v = (dr, dtheta, dphi), c = E. Kerr (units of M):
+    // v = (p_r, p_theta, 0), c = b.
+    void state_rhs(vec3 x, vec3 v, float c, out vec3 dx, out vec3 dv) {
+        if (useKerr != 0) {
+            vec2 dp;
+            kerr_rhs(x, v.xy, c, dx, dp);
+            dv = vec3(dp, 0.0);
+        } else {
+            float d2r, d2theta, d2phi;
+            geodesic_rhs(x.x, x.y, v.x, v.y, v.z, c, d2r, d2theta, d2phi);
+            dx = v;
+            dv = vec3(d2r, d2theta, d2phi);
+        }
+    }
+
+    // Dormand-Prince 5(4) step, as `rk45_step` in tracer.rs. Advances the state
+    // and returns true when the embedded error is within tolerance; h always
+    // becomes the suggested next step.
+    bool rk45_step(inout vec3 x, inout vec3 v, float c, inout float h) {
+        vec3 k1x, k2x, k3x, k4x, k5x, k6x, k7x;
+        vec3 k1v, k2v, k3v, k4v, k5v, k6v, k7v;
+        state_rhs(x, v, c, k1x, k1v);
+        state_rhs(x + h*(k1x/5.0),
+                  v + h*(k1v/5.0), c, k2x, k2v);
+        state_rhs(x + h*(3.0/40.0*k1x + 9.0/40.0*k2x),
+                  v + h*(3.0/40.0*k1v + 9.0/40.0*k2v), c, k3x, k3v);
+        state_rhs(x + h*(44.0/45.0*k1x - 56.0/15.0*k2x + 32.0/9.0*k3x),
+                  v + h*(44.0/45.0*k1v - 56.0/15.0*k2v + 32.0/9.0*k3v), c, k4x, k4v);
+        state_rhs(x + h*(19372.0/6561.0*k1x - 25360.0/2187.0*k2x + 64448.0/6561.0*k3x
+                         - 212.0/729.0*k4x),
+                  v + h*(19372.0/6561.0*k1v - 25360.0/2187.0*k2v + 64448.0/6561.0*k3v
+                         - 212.0/729.0*k4v), c, k5x, k5v);
+        state_rhs(x + h*(9017.0/3168.0*k1x - 355.0/33.0*k2x + 46732.0/5247.0*k3x
+                         + 49.0/176.0*k4x - 5103.0/18656.0*k5x),
+                  v + h*(9017.0/3168.0*k1v - 355.0/33.0*k2v + 46732.0/5247.0*k3v
+                         + 49.0/176.0*k4v - 5103.0/18656.0*k5v), c, k6x, k6v);
+        vec3 x5 = x + h*(35.0/384.0*k1x + 500.0/1113.0*k3x + 125.0/192.0*k4x
+                         - 2187.0/6784.0*k5x + 11.0/84.0*k6x);
+        vec3 v5 = v + h*(35.0/384.0*k1v + 500.0/1113.0*k3v + 125.0/192.0*k4v
+                         - 2187.0/6784.0*k5v + 11.0/84.0*k6v);
+        state_rhs(x5, v5, c, k7x, k7v);
+
+        // Fifth- minus fourth-order solution
+        vec3 ex = h*(71.0/57600.0*k1x - 71.0/16695.0*k3x + 71.0/1920.0*k4x
+                     - 17253.0/339200.0*k5x + 22.0/525.0*k6x - k7x/40.0);
+        vec3 ev = h*(71.0/57600.0*k1v - 71.0/16695.0*k3v + 71.0/1920.0*k4v
+                     - 17253.0/339200.0*k5v + 22.0/525.0*k6v - k7v/40.0);
+
+        // Component scales as in `Metric::error_scale`; f32 limits the tolerance
+        float r  = x.x;
+        vec3 sx  = vec3(r, 1.0, 1.0);
+        vec3 sv  = (useKerr != 0) ? vec3(1.0, r, r) : vec3(1.0, 1.0/r, 1.0/r);
+        vec3 qx  = abs(ex) / sx;
+        vec3 qv  = abs(ev) / sv;
+        float err = max(max(max(qx.x, qx.y), qx.z), max(max(qv.x, qv.y), qv.z))
+                    / max(tolerance, 1e-6);
+
+        bool accepted = err <= 1.0;
+        if (accepted) {
+            x = x5;
+            v = v5;
+        }
+        h *= clamp(err > 0.0 ? 0.9 * pow(err, -0.2) : 5.0, 0.2, 5.0);
+        return accepted;
+    }
+
     // Procedural starfield sampled from a lensed escape direction.
     // Returns an RGB colour; black when no star lands in that cell.
     vec3 starfield(vec3 dir) {
@@ -160,62 +293,123 @@ pub const QUAD_FRAG_GEODESIC: &str = r#"
         float dphi   = (-sin_p*dx                      + cos_p*dz) / (r * sin_t);
         if (abs(sin_theta_raw) < POLE_EPS) dphi = 0.0;
 
+        // Null condition: f (dt/dl)^2 = dr^2/f + r^2 (dtheta^2 + sin^2 dphi^2)
         float f     = 1.0 - r_s / r;
-        float dt_dl = sqrt(dr*dr/f + r*r*(dtheta*dtheta + sin_t*sin_t*dphi*dphi));
+        float dt_dl = sqrt((dr*dr/f + r*r*(dtheta*dtheta + sin_t*sin_t*dphi*dphi)) / f);
         float E     = f * dt_dl;
 
-        float disk_r1 = r_s * DISK_INNER;
+        // Kerr state lives in units of M to keep the f32 products in range
+        float M = 0.5 * r_s;
+        vec3  kx = vec3(r / M, theta, phi);
+        vec2  kp;
+        float kb;
+        float horizon = r_s;
+        if (useKerr != 0) {
+            kerr_init(kx.x, theta, dr, dtheta * M, dphi * M, kp, kb);
+            horizon = M * (1.0 + sqrt(max(1.0 - spin*spin, 0.0))) * (1.0 + HORIZON_EPS);
+        }
+
+        float disk_r1 = diskInner;
         float disk_r2 = r_s * DISK_OUTER;
-        float prev_y  = camPos.y;
+        vec3  prevP   = camPos;
         bool  escaped = false;
+        float hAdaptive = 0.0;  // RK45 step, carried between iterations
 
         for (int i = 0; i < MAX_STEPS; i++) {
-            if (r <= r_s) {
+            if (r <= horizon) {
                 FragColor = vec4(0.0, 0.0, 0.0, 1.0);
                 return;
             }
 
-            float proximity = clamp((r - r_s) / (5.0 * r_s), 0.0, 1.0);
+            float proximity = clamp((r - horizon) / (5.0 * r_s), 0.0, 1.0);
             float h = D_LAMBDA * (0.02 + 0.98 * proximity);
 
-            if (useRK4 != 0) {
-                rk4_step(r, theta, phi, dr, dtheta, dphi, E, h);
+            if (integrator == 2) {
+                if (hAdaptive == 0.0) hAdaptive = h;
+                bool accepted;
+                if (useKerr != 0) {
+                    vec3  kv = vec3(kp, 0.0);
+                    float hM = hAdaptive / M;
+                    accepted = rk45_step(kx, kv, kb, hM);
+                    hAdaptive = hM * M;
+                    kp = kv.xy;
+                    r = kx.x * M; theta = kx.y; phi = kx.z;
+                } else {
+                    vec3 sx = vec3(r, theta, phi);
+                    vec3 sv = vec3(dr, dtheta, dphi);
+                    accepted = rk45_step(sx, sv, E, hAdaptive);
+                    r = sx.x; theta = sx.y; phi = sx.z;
+                    dr = sv.x; dtheta = sv.y; dphi = sv.z;
+                    if (abs(sin(theta)) < POLE_EPS) dphi = 0.0;
+                }
+                hAdaptive = min(hAdaptive, RK45_MAX_STEP);
+                if (!accepted) continue;
+            } else if (useKerr != 0) {
+                if (integrator == 1) {
+                    kerr_rk4_step(kx, kp, kb, h / M);
+                } else {
+                    kerr_euler_step(kx, kp, kb, h / M);
+                }
+                r = kx.x * M; theta = kx.y; phi = kx.z;
             } else {
-                euler_step(r, theta, phi, dr, dtheta, dphi, E, h);
+                if (integrator == 1) {
+                    rk4_step(r, theta, phi, dr, dtheta, dphi, E, h);
+                } else {
+                    euler_step(r, theta, phi, dr, dtheta, dphi, E, h);
+                }
+                if (abs(sin(theta)) < POLE_EPS) dphi = 0.0;
             }
-            if (abs(sin(theta)) < POLE_EPS) dphi = 0.0;
 
             float sin_th = sin(theta);
             float cart_x = r * sin_th * cos(phi);
             float cart_y = r * cos(theta);
             float cart_z = r * sin_th * sin(phi);
 
-            if (prev_y * cart_y < 0.0) {
-                float xz_r = sqrt(cart_x*cart_x + cart_z*cart_z);
+            // Disk hit test: the step crosses the equatorial plane inside the annulus
+            vec3 P = vec3(cart_x, cart_y, cart_z);
+            if (prevP.y * cart_y < 0.0) {
+                vec3 hit = mix(prevP, P, prevP.y / (prevP.y - cart_y));
+                float xz_r = length(hit.xz);
                 if (xz_r >= disk_r1 && xz_r <= disk_r2) {
                     float t = (xz_r - disk_r1) / (disk_r2 - disk_r1);
                     FragColor = vec4(1.0, 0.55 + 0.45*t, 0.1*(1.0 - t), 1.0);
                     return;
                 }
             }
-            prev_y = cart_y;
 
-            // Scene object sphere intersection (headlamp: camera = light source)
-            vec3 P = vec3(cart_x, cart_y, cart_z);
+            // Scene object sphere intersection along the step, in units of the
+            // sphere radius to keep the f32 products in range
+            // (headlamp: camera = light source)
             for (int j = 0; j < numObjects; j++) {
-                if (distance(P, objPosRadius[j].xyz) <= objPosRadius[j].w) {
-                    vec3 N = normalize(P - objPosRadius[j].xyz);
-                    vec3 V = normalize(camPos - P);
+                vec3  C  = objPosRadius[j].xyz;
+                float R  = objPosRadius[j].w;
+                vec3  D  = (P - prevP) / R;
+                vec3  F  = (prevP - C) / R;
+                float qa = dot(D, D);
+                float qb = 2.0 * dot(F, D);
+                float qc = dot(F, F) - 1.0;
+                float disc = qb*qb - 4.0*qa*qc;
+                float s = (qc <= 0.0) ? 0.0 : (-qb - sqrt(max(disc, 0.0))) / (2.0*qa);
+                if (qc <= 0.0 || (disc >= 0.0 && s >= 0.0 && s <= 1.0)) {
+                    vec3 H = mix(prevP, P, s);
+                    vec3 N = normalize(H - C);
+                    vec3 V = normalize(camPos - H);
                     float intensity = 0.1 + 0.9 * max(dot(N, V), 0.0);
                     FragColor = vec4(objColor[j].rgb * intensity, 1.0);
                     return;
                 }
             }
+            prevP = P;
 
             if (r > ESCAPE_R) { escaped = true; break; }
         }
 
         if (escaped) {
+            if (useKerr != 0) {
+                vec3 kdx; vec2 kdp;
+                kerr_rhs(kx, kp, kb, kdx, kdp);
+                dr = kdx.x; dtheta = kdx.y / M; dphi = kdx.z / M;
+            }
             // Reconstruct Cartesian direction from final spherical velocity
             float sin_th_e = sin(theta);
             float cos_th_e = cos(theta);
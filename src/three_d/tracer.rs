@@ -0,0 +1,431 @@
+//! CPU reference geodesic ray tracer: a Rust port of `QUAD_FRAG_GEODESIC`.
+//!
+//! Same camera, black hole and scene objects as the GPU path, integrated in f64
+//! per pixel (in parallel with rayon) into an RGB buffer. Useful without an
+//! OpenGL context, and as the oracle the shader output is checked against.
+
+use rayon::prelude::*;
+
+use super::camera::Camera;
+use super::constants::{D_LAMBDA, DISK_OUTER, ESCAPE_R, MAX_STEPS, RK45_MAX_STEP, RK45_TOLERANCE};
+use super::math::camera_basis;
+use super::metric::{Metric, Spacetime, State};
+use super::scene::{BlackHole, SceneObject};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    Euler,
+    Rk4,
+    /// Dormand–Prince 5(4) with step size control from `TraceSettings::tolerance`.
+    Rk45,
+}
+
+impl Integrator {
+    /// Euler → RK4 → RK45 → Euler.
+    pub fn next(self) -> Self {
+        match self {
+            Integrator::Euler => Integrator::Rk4,
+            Integrator::Rk4 => Integrator::Rk45,
+            Integrator::Rk45 => Integrator::Euler,
+        }
+    }
+}
+
+/// Per-render settings that are uniforms (or constants) on the GPU side.
+#[derive(Clone, Copy, Debug)]
+pub struct TraceSettings {
+    pub integrator: Integrator,
+    pub spacetime: Spacetime,
+    pub fov_y_deg: f32,
+    /// Per-step error tolerance for `Integrator::Rk45`, relative to `Metric::error_scale`.
+    pub tolerance: f64,
+}
+
+impl Default for TraceSettings {
+    fn default() -> Self {
+        Self {
+            integrator: Integrator::Euler,
+            spacetime: Spacetime::Schwarzschild,
+            fov_y_deg: 60.0,
+            tolerance: RK45_TOLERANCE,
+        }
+    }
+}
+
+/// Integration cost, per ray or summed over a frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepStats {
+    pub rays: u64,
+    /// Accepted steps.
+    pub steps: u64,
+    /// Steps rejected by the RK45 error control (always 0 for fixed-step modes).
+    pub rejected: u64,
+}
+
+impl std::ops::Add for StepStats {
+    type Output = Self;
+
+    fn add(self, o: Self) -> Self {
+        Self {
+            rays: self.rays + o.rays,
+            steps: self.steps + o.steps,
+            rejected: self.rejected + o.rejected,
+        }
+    }
+}
+
+impl std::fmt::Display for StepStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let rays = self.rays.max(1) as f64;
+        write!(
+            f,
+            "{} rays, {} steps ({:.1}/ray), {} rejected ({:.1}/ray)",
+            self.rays,
+            self.steps,
+            self.steps as f64 / rays,
+            self.rejected,
+            self.rejected as f64 / rays,
+        )
+    }
+}
+
+/// Colour and cost of one traced ray.
+pub struct RayTrace {
+    pub color: [f32; 3],
+    pub stats: StepStats,
+}
+
+/// 8-bit RGB image, rows stored top to bottom.
+pub struct RgbImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl RgbImage {
+    /// Flat `RGBRGB...` bytes, as expected by image encoders.
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+}
+
+fn add_scaled(y: &State, k: &State, h: f64) -> State {
+    std::array::from_fn(|i| y[i] + h * k[i])
+}
+
+/// Euler step - take step using derivative at start of interval
+pub fn euler_step(y: &mut State, h: f64, rhs: impl Fn(&State) -> State) {
+    *y = add_scaled(y, &rhs(y), h);
+}
+
+/// RK4 step - take step using weighted average of derivatives at start, midpoint, and end
+pub fn rk4_step(y: &mut State, h: f64, rhs: impl Fn(&State) -> State) {
+    let k1 = rhs(y);
+    let k2 = rhs(&add_scaled(y, &k1, 0.5 * h));
+    let k3 = rhs(&add_scaled(y, &k2, 0.5 * h));
+    let k4 = rhs(&add_scaled(y, &k3, h));
+    for i in 0..6 {
+        y[i] += h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+    }
+}
+
+/// Dormand–Prince 5(4) step. Advances `y` with the fifth-order solution when the
+/// embedded error estimate, measured against `scale`, is within `tol`, and returns
+/// whether it did. Either way `h` becomes the suggested next step size.
+pub fn rk45_step(
+    y: &mut State,
+    h: &mut f64,
+    tol: f64,
+    scale: &State,
+    rhs: impl Fn(&State) -> State,
+) -> bool {
+    const A: [[f64; 6]; 6] = [
+        [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+        [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+        [
+            19372.0 / 6561.0,
+            -25360.0 / 2187.0,
+            64448.0 / 6561.0,
+            -212.0 / 729.0,
+            0.0,
+            0.0,
+        ],
+        [
+            9017.0 / 3168.0,
+            -355.0 / 33.0,
+            46732.0 / 5247.0,
+            49.0 / 176.0,
+            -5103.0 / 18656.0,
+            0.0,
+        ],
+        [
+            35.0 / 384.0,
+            0.0,
+            500.0 / 1113.0,
+            125.0 / 192.0,
+            -2187.0 / 6784.0,
+            11.0 / 84.0,
+        ],
+    ];
+    // Fifth- minus fourth-order weights
+    const E: [f64; 7] = [
+        71.0 / 57600.0,
+        0.0,
+        -71.0 / 16695.0,
+        71.0 / 1920.0,
+        -17253.0 / 339200.0,
+        22.0 / 525.0,
+        -1.0 / 40.0,
+    ];
+
+    let mut k = [[0.0; 6]; 7];
+    k[0] = rhs(y);
+    for s in 0..6 {
+        let stage =
+            std::array::from_fn(|i| y[i] + *h * (0..=s).map(|j| A[s][j] * k[j][i]).sum::<f64>());
+        k[s + 1] = rhs(&stage);
+    }
+
+    let mut err: f64 = 0.0;
+    for i in 0..6 {
+        let e: f64 = (0..7).map(|j| E[j] * k[j][i]).sum();
+        err = err.max((*h * e).abs() / (tol * scale[i]));
+    }
+
+    let accepted = err <= 1.0;
+    if accepted {
+        for i in 0..6 {
+            // The last stage is evaluated at the fifth-order solution
+            y[i] += *h * (0..6).map(|j| A[5][j] * k[j][i]).sum::<f64>();
+        }
+    }
+    let factor = if err > 0.0 { 0.9 * err.powf(-0.2) } else { 5.0 };
+    *h *= factor.clamp(0.2, 5.0);
+    accepted
+}
+
+/// Cartesian position of a state.
+pub fn to_cartesian(y: &State) -> [f64; 3] {
+    let [r, theta, phi, ..] = *y;
+    [
+        r * theta.sin() * phi.cos(),
+        r * theta.cos(),
+        r * theta.sin() * phi.sin(),
+    ]
+}
+
+/// Cartesian direction of travel, from a state and its derivative `dy`.
+pub fn direction(y: &State, dy: &State) -> [f64; 3] {
+    let [r, theta, phi, ..] = *y;
+    let [dr, dtheta, dphi, ..] = *dy;
+    let (sin_th, cos_th) = theta.sin_cos();
+    let (sin_ph, cos_ph) = phi.sin_cos();
+    normalize([
+        dr * sin_th * cos_ph + r * (dtheta * cos_th * cos_ph - dphi * sin_th * sin_ph),
+        dr * cos_th - r * dtheta * sin_th,
+        dr * sin_th * sin_ph + r * (dtheta * cos_th * sin_ph + dphi * sin_th * cos_ph),
+    ])
+}
+
+/// Integrates one ray through `metric` and returns its linear RGB colour.
+pub fn trace_ray(
+    metric: &dyn Metric,
+    origin: [f64; 3],
+    dir: [f64; 3],
+    r_s: f64,
+    objects: &[SceneObject],
+    settings: &TraceSettings,
+) -> RayTrace {
+    let mut y = metric.initial_state(origin, dir);
+    let conserved = metric.conserved(&y);
+    let rhs = |s: &State| metric.rhs(s, &conserved);
+    let horizon = metric.capture_radius();
+
+    let disk_r1 = metric.disk_inner_radius();
+    let disk_r2 = r_s * DISK_OUTER;
+    let mut prev = origin;
+    let mut stats = StepStats {
+        rays: 1,
+        ..Default::default()
+    };
+    let done = |color, stats| RayTrace { color, stats };
+    // RK45 carries its step size between iterations, seeded from the fixed-step rule
+    let mut h_adaptive = 0.0;
+
+    for _ in 0..MAX_STEPS {
+        if metric.inside_horizon(&y) {
+            return done([0.0; 3], stats);
+        }
+
+        let proximity = ((y[0] - horizon) / (5.0 * r_s)).clamp(0.0, 1.0);
+        let h = D_LAMBDA * (0.02 + 0.98 * proximity);
+
+        match settings.integrator {
+            Integrator::Euler => euler_step(&mut y, h, rhs),
+            Integrator::Rk4 => rk4_step(&mut y, h, rhs),
+            Integrator::Rk45 => {
+                if h_adaptive == 0.0 {
+                    h_adaptive = h;
+                }
+                let scale = metric.error_scale(&y);
+                let accepted = rk45_step(&mut y, &mut h_adaptive, settings.tolerance, &scale, rhs);
+                h_adaptive = h_adaptive.min(RK45_MAX_STEP);
+                if !accepted {
+                    stats.rejected += 1;
+                    continue;
+                }
+            }
+        }
+        stats.steps += 1;
+        metric.post_step(&mut y);
+
+        let p = to_cartesian(&y);
+
+        // Disk hit test: the step crosses the equatorial plane inside the annulus
+        if prev[1] * p[1] < 0.0 {
+            let s = prev[1] / (prev[1] - p[1]);
+            let hit = lerp(prev, p, s);
+            let xz_r = (hit[0] * hit[0] + hit[2] * hit[2]).sqrt();
+            if xz_r >= disk_r1 && xz_r <= disk_r2 {
+                let t = ((xz_r - disk_r1) / (disk_r2 - disk_r1)) as f32;
+                return done([1.0, 0.55 + 0.45 * t, 0.1 * (1.0 - t)], stats);
+            }
+        }
+
+        // Scene object sphere intersection (headlamp: camera = light source)
+        for obj in objects {
+            let c = obj.position.map(|v| v as f64);
+            if let Some(hit) = segment_sphere(prev, p, c, obj.radius as f64) {
+                let n = normalize(sub(hit, c));
+                let v = normalize(sub(origin, hit));
+                let intensity = 0.1 + 0.9 * dot(n, v).max(0.0) as f32;
+                return done(obj.color.map(|ch| ch * intensity), stats);
+            }
+        }
+        prev = p;
+
+        if y[0] > ESCAPE_R {
+            let d = direction(&y, &rhs(&y));
+            return done(starfield([d[0] as f32, d[1] as f32, d[2] as f32]), stats);
+        }
+    }
+
+    done([0.0; 3], stats)
+}
+
+/// Procedural starfield sampled from a lensed escape direction.
+/// Kept in f32 so the cell hashes agree with the GPU version.
+pub fn starfield(dir: [f32; 3]) -> [f32; 3] {
+    let fl = dir.map(|d| (d * 300.0).floor());
+    let hash = |k: [f32; 3]| {
+        let d = fl[0] * k[0] + fl[1] * k[1] + fl[2] * k[2];
+        fract(d.sin() * 43_758.547)
+    };
+    let h1 = hash([127.1, 311.7, 74.7]);
+    let h2 = hash([269.5, 183.3, 246.1]);
+    let h3 = hash([113.5, 271.9, 124.6]);
+    if h1 > 0.0001 {
+        return [0.0; 3]; // ~0.01 % of cells host a star
+    }
+    let brightness = 0.3 + 0.7 * h2;
+    let tint = if h3 < 0.33 {
+        [0.8, 0.9, 1.0] // blue-white
+    } else if h3 < 0.66 {
+        [1.0, 1.0, 1.0] // white
+    } else {
+        [1.0, 0.85, 0.6] // warm orange
+    };
+    tint.map(|c| c * brightness)
+}
+
+/// Renders a full frame on the CPU, one rayon task per pixel, and returns it
+/// with the summed integration cost.
+pub fn render(
+    camera: &Camera,
+    bh: &BlackHole,
+    objects: &[SceneObject],
+    settings: &TraceSettings,
+    width: u32,
+    height: u32,
+) -> (RgbImage, StepStats) {
+    let pos = camera.position();
+    let (right, up, fwd) = camera_basis(pos);
+    let origin = pos.map(|v| v as f64);
+    let tan_hfov = (settings.fov_y_deg.to_radians() * 0.5).tan();
+    let aspect = width as f32 / height as f32;
+    let metric = settings.spacetime.metric(bh);
+    let r_s = bh.r_s as f64;
+
+    let traces: Vec<RayTrace> = (0..width * height)
+        .into_par_iter()
+        .map(|i| {
+            // Pixel centres, with texture v = 0 at the bottom edge like the quad
+            let tx = ((i % width) as f32 + 0.5) / width as f32;
+            let ty = 1.0 - ((i / width) as f32 + 0.5) / height as f32;
+            let u = (tx * 2.0 - 1.0) * aspect * tan_hfov;
+            let v = (ty * 2.0 - 1.0) * tan_hfov;
+            let dir = normalize(std::array::from_fn(|k| {
+                (u * right[k] + v * up[k] + fwd[k]) as f64
+            }));
+            trace_ray(metric.as_ref(), origin, dir, r_s, objects, settings)
+        })
+        .collect();
+
+    let stats = traces
+        .iter()
+        .fold(StepStats::default(), |acc, t| acc + t.stats);
+    let pixels = traces
+        .iter()
+        .map(|t| t.color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+        .collect();
+
+    let image = RgbImage {
+        width,
+        height,
+        pixels,
+    };
+    (image, stats)
+}
+
+/// First point where the segment `a`→`b` enters the sphere, if it does.
+fn segment_sphere(a: [f64; 3], b: [f64; 3], center: [f64; 3], radius: f64) -> Option<[f64; 3]> {
+    let d = sub(b, a);
+    let f = sub(a, center);
+    let c = dot(f, f) - radius * radius;
+    if c <= 0.0 {
+        return Some(a);
+    }
+    let (qa, qb) = (dot(d, d), 2.0 * dot(f, d));
+    let disc = qb * qb - 4.0 * qa * c;
+    if disc < 0.0 {
+        return None;
+    }
+    let s = (-qb - disc.sqrt()) / (2.0 * qa);
+    (0.0..=1.0).contains(&s).then(|| lerp(a, b, s))
+}
+
+fn lerp(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn fract(x: f32) -> f32 {
+    x - x.floor()
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn length(v: [f64; 3]) -> f64 {
+    dot(v, v).sqrt()
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let len = length(v);
+    [v[0] / len, v[1] / len, v[2] / len]
+}